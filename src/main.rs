@@ -1,24 +1,47 @@
-use clap::{Parser, ValueEnum};
-use lopdf::Document;
-use std::path::PathBuf;
+use clap::{Parser, Subcommand, ValueEnum};
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 /// Defines the splitting mode for the PDF.
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
 enum SplitMode {
     /// Split the PDF into a specified number of equal chunks.
     NumChunks,
     /// Split the PDF into chunks of a specified number of pages.
     PageSize,
+    /// Split the PDF into chunks capped at a target serialized size.
+    SizeBytes,
 }
 
 /// A command line tool to chunk and save a given pdf file into a new folder.
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
-struct Args {
-    /// Path to the input PDF file
-    #[arg(short, long)]
-    input: PathBuf,
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Commands {
+    /// Split a PDF into multiple chunk files.
+    Split(SplitArgs),
+    /// Reassemble `chunk_*.pdf` files back into one PDF, keeping every page.
+    /// Document outlines (bookmarks) are not carried over.
+    Merge(MergeArgs),
+}
+
+#[derive(Parser, Debug, Clone)]
+struct SplitArgs {
+    /// Path(s) to the input PDF file(s). Pass --max-chunks to distribute a
+    /// chunk budget across multiple inputs proportionally to their page counts.
+    #[arg(short, long = "input", num_args = 1.., required = true)]
+    inputs: Vec<PathBuf>,
 
     /// Path to the output directory
     #[arg(short, long)]
@@ -35,57 +58,566 @@ struct Args {
     /// Number of equal chunks (used with --mode num-chunks)
     #[arg(long)]
     num_chunks: Option<usize>,
+
+    /// Maximum serialized size per chunk (used with --mode size-bytes), e.g. `5MB` or `512KB`
+    #[arg(long, value_parser = parse_byte_size)]
+    max_bytes: Option<usize>,
+
+    /// Total chunk budget to distribute across all --input files, proportionally
+    /// to their page counts. Required when more than one --input is given.
+    #[arg(long)]
+    max_chunks: Option<usize>,
+
+    /// Instead of writing `chunk_N.pdf` to disk, pipe each chunk's serialized
+    /// bytes to this shell command's stdin. `$FILE` expands to the path the
+    /// chunk would otherwise have been written to, e.g. `--filter='gzip > $FILE.gz'`.
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Content-address chunks by the SHA-256 of their serialized bytes
+    /// (`output/<first-2-hex>/<full-hex>.pdf`), skipping the write when a
+    /// byte-identical chunk has already been stored. Not compatible with `--filter`.
+    #[arg(long)]
+    dedup: bool,
+}
+
+#[derive(Parser, Debug, Clone)]
+struct MergeArgs {
+    /// Directory containing `chunk_*.pdf` files and the `manifest.json` sidecar written by `split`
+    #[arg(short, long)]
+    input: PathBuf,
+
+    /// Path to the reassembled output PDF
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+/// A single chunk's entry in the `manifest.json` sidecar written by `split`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ChunkManifestEntry {
+    file: String,
+    start_page: u32,
+    end_page: u32,
+    sha256: String,
+}
+
+/// The `manifest.json` sidecar written alongside a split's chunk files, used
+/// by `merge` to verify every chunk is present and byte-for-byte intact.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct Manifest {
+    chunks: Vec<ChunkManifestEntry>,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Commands::Split(args) => run_split(&args),
+        Commands::Merge(args) => run_merge(&args),
+    }
+}
+
+fn run_split(args: &SplitArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if args.filter.is_some() && args.dedup {
+        return Err("--filter and --dedup cannot be used together".into());
+    }
+
+    if let Some(max_chunks) = args.max_chunks {
+        return run_split_batch(args, max_chunks);
+    }
+
+    if args.inputs.len() > 1 {
+        return Err("multiple --input files require --max-chunks to set a chunk budget".into());
+    }
+
+    run_split_one(&args.inputs[0], &args.output, args).map(|_| ())
+}
+
+/// Splits multiple input PDFs under a single chunk budget, distributed across
+/// them proportionally to page count (the `chunkify_multiple` approach): each
+/// file's share is `ceil(pages_i / avg_pages_per_chunk)`, trimmed so the total
+/// never exceeds `max_chunks`. Zero-page inputs are skipped entirely and don't
+/// count against the budget; every other input is left with at least one chunk.
+fn run_split_batch(args: &SplitArgs, max_chunks: usize) -> Result<(), Box<dyn std::error::Error>> {
+    if max_chunks == 0 {
+        return Err("--max-chunks must be greater than zero".into());
+    }
+
+    // --max-chunks always allocates via balanced num-chunks partitioning, so
+    // any --mode/--chunk-size/--max-bytes the caller also passed is ignored.
+    if args.mode != SplitMode::NumChunks {
+        eprintln!(
+            "Warning: --max-chunks overrides --mode to num-chunks; the requested {:?} mode (and any --chunk-size/--max-bytes) will be ignored",
+            args.mode
+        );
+    }
+
+    let page_counts: Vec<usize> = args
+        .inputs
+        .iter()
+        .map(|path| Ok::<_, Box<dyn std::error::Error>>(Document::load(path)?.get_pages().len()))
+        .collect::<Result<_, _>>()?;
+
+    let non_empty_inputs = page_counts.iter().filter(|&&pages| pages > 0).count();
+    if max_chunks < non_empty_inputs {
+        return Err(format!(
+            "--max-chunks ({}) is smaller than the number of non-empty --input files ({})",
+            max_chunks, non_empty_inputs
+        )
+        .into());
+    }
 
+    let allocations = allocate_chunk_budget(&page_counts, max_chunks);
+
+    fs::create_dir_all(&args.output)?;
+
+    println!("Distributing a budget of {} chunk(s) across {} input file(s):", max_chunks, args.inputs.len());
+    let mut total_chunks_written = 0;
+    for (index, ((input, pages), chunks)) in args.inputs.iter().zip(page_counts.iter()).zip(allocations.iter()).enumerate() {
+        // Index-prefixed so two inputs sharing a basename (or the same path
+        // given twice) never collide on the same sub-directory and clobber
+        // each other's chunks.
+        let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("input");
+        let sub_output = args.output.join(format!("{:02}_{}", index, stem));
+
+        if *chunks == 0 {
+            println!("  {:?}: 0 page(s), skipping (no chunks allocated)", input);
+            continue;
+        }
+        println!("  {:?}: {} page(s) -> {} chunk(s) in {:?}", input, pages, chunks, sub_output);
+
+        let mut per_file_args = args.clone();
+        per_file_args.mode = SplitMode::NumChunks;
+        per_file_args.num_chunks = Some(*chunks);
+
+        total_chunks_written += run_split_one(input, &sub_output, &per_file_args)?;
+    }
+
+    println!("Wrote {} chunk(s) total across {} input file(s)", total_chunks_written, args.inputs.len());
+
+    Ok(())
+}
+
+/// Splits a single input PDF into `output_dir`, following `args`' mode and
+/// options. Returns the number of chunks written.
+fn run_split_one(input: &Path, output_dir: &Path, args: &SplitArgs) -> Result<usize, Box<dyn std::error::Error>> {
     // Open the input PDF file using lopdf
-    let doc = Document::load(&args.input)?;
+    let doc = Document::load(input)?;
     let num_pages = doc.get_pages().len();
 
     // Create the output directory if it doesn't exist
-    fs::create_dir_all(&args.output)?;
+    fs::create_dir_all(output_dir)?;
 
-    let chunk_size = match args.mode {
-        SplitMode::PageSize => args.chunk_size,
+    let chunk_lengths = match args.mode {
+        SplitMode::PageSize => page_size_chunk_lengths(num_pages, args.chunk_size),
         SplitMode::NumChunks => {
             let num_chunks = args.num_chunks.unwrap_or(3); // Default to 3 chunks if not specified
             if num_chunks == 0 {
                 return Err("Number of chunks cannot be zero.".into());
             }
-            (num_pages + num_chunks - 1) / num_chunks // Calculate chunk size
+            if num_chunks > num_pages {
+                return Err(format!(
+                    "--num-chunks ({}) cannot exceed the document's page count ({})",
+                    num_chunks, num_pages
+                )
+                .into());
+            }
+            balanced_chunk_lengths(num_pages, num_chunks)
+        }
+        SplitMode::SizeBytes => {
+            let max_bytes = args.max_bytes.ok_or("--max-bytes is required for --mode size-bytes")?;
+            if max_bytes == 0 {
+                return Err("--max-bytes must be greater than zero".into());
+            }
+            size_bytes_chunk_lengths(&doc, num_pages, max_bytes)?
         }
     };
 
-    // Chunk and save the PDF
-    for (chunk_index, start_page) in (0..num_pages).step_by(chunk_size).enumerate() {
-        let end_page = (start_page + chunk_size).min(num_pages);
-        let output_path = args.output.join(format!("chunk_{}.pdf", chunk_index + 1));
+    // Chunk, save, and record each chunk in the manifest
+    let mut manifest = Manifest::default();
+    let mut dedup_index: BTreeMap<String, String> = BTreeMap::new();
+    let mut unique_chunks = 0usize;
+    let mut deduped_chunks = 0usize;
+    let mut bytes_saved = 0u64;
+    let mut start_page = 0;
+    for (chunk_index, length) in chunk_lengths.into_iter().enumerate() {
+        let end_page = start_page + length;
+        let file_name = format!("chunk_{}.pdf", chunk_index + 1);
+        let output_path = output_dir.join(&file_name);
+
+        // Clone the original document, keeping only this chunk's pages
+        let mut chunk_doc = extract_chunk(&doc, num_pages, start_page, end_page);
+
+        // Serialize the chunk in memory so we can hash it before writing or piping it out
+        let bytes = serialize_document(&mut chunk_doc)?;
+        let digest = sha256_hex(&bytes);
+
+        let stored_file = if let Some(filter) = &args.filter {
+            pipe_chunk_to_filter(filter, &output_path, &bytes)?;
+            println!("Piped chunk {} (pages {} to {}) through filter", chunk_index + 1, start_page + 1, end_page);
+            file_name.clone()
+        } else if args.dedup {
+            let (chunk_path, created) = write_deduped_chunk(output_dir, &digest, &bytes)?;
+            dedup_index.insert(file_name.clone(), digest.clone());
+            if created {
+                unique_chunks += 1;
+                println!("Saved chunk {} (pages {} to {}) to {:?}", chunk_index + 1, start_page + 1, end_page, chunk_path);
+            } else {
+                deduped_chunks += 1;
+                bytes_saved += bytes.len() as u64;
+                println!("Chunk {} (pages {} to {}) matches {:?}, skipping write", chunk_index + 1, start_page + 1, end_page, chunk_path);
+            }
+            chunk_path.strip_prefix(output_dir)?.to_string_lossy().into_owned()
+        } else {
+            fs::write(&output_path, &bytes)?;
+            println!("Saved chunk {} (pages {} to {}) to {:?}", chunk_index + 1, start_page + 1, end_page, output_path);
+            file_name.clone()
+        };
+
+        manifest.chunks.push(ChunkManifestEntry {
+            file: stored_file,
+            start_page: start_page as u32 + 1,
+            end_page: end_page as u32,
+            sha256: digest,
+        });
+
+        start_page = end_page;
+    }
+
+    let manifest_path = output_dir.join("manifest.json");
+    fs::write(&manifest_path, serde_json::to_vec_pretty(&manifest)?)?;
+    println!("Wrote manifest for {} chunk(s) to {:?}", manifest.chunks.len(), manifest_path);
+
+    if args.dedup {
+        let index_path = output_dir.join("chunks.index");
+        fs::write(&index_path, serde_json::to_vec_pretty(&dedup_index)?)?;
+        println!(
+            "Dedup summary: {} unique, {} deduplicated, {} bytes saved (index: {:?})",
+            unique_chunks, deduped_chunks, bytes_saved, index_path
+        );
+    }
+
+    Ok(manifest.chunks.len())
+}
+
+fn run_merge(args: &MergeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_path = args.input.join("manifest.json");
+    let manifest_bytes = fs::read(&manifest_path)
+        .map_err(|e| format!("failed to read manifest at {:?}: {}", manifest_path, e))?;
+    let manifest: Manifest = serde_json::from_slice(&manifest_bytes)?;
+
+    let mut chunks = manifest.chunks.clone();
+    chunks.sort_by_key(|c| c.start_page);
+
+    let mut docs = Vec::with_capacity(chunks.len());
+    for chunk in &chunks {
+        let chunk_path = args.input.join(&chunk.file);
+        let bytes = fs::read(&chunk_path)
+            .map_err(|e| format!("missing chunk {:?} listed in manifest: {}", chunk_path, e))?;
 
-        // Clone the original document for the current chunk
-        let mut chunk_doc = doc.clone();
+        let actual_digest = sha256_hex(&bytes);
+        if actual_digest != chunk.sha256 {
+            return Err(format!(
+                "checksum mismatch for {:?}: manifest says {}, on-disk bytes hash to {}",
+                chunk_path, chunk.sha256, actual_digest
+            )
+            .into());
+        }
+
+        docs.push(Document::load_mem(&bytes)?);
+    }
 
-        // Determine pages to keep (1-based)
-        let pages_to_keep: Vec<u32> = (start_page as u32 + 1..=end_page as u32).collect();
-        let all_pages: Vec<u32> = (1..=num_pages as u32).collect();
+    let mut merged = merge_documents(docs)?;
+    let bytes = serialize_document(&mut merged)?;
+    fs::write(&args.output, &bytes)?;
+
+    println!("Merged {} chunk(s) into {:?}", chunks.len(), args.output);
+
+    Ok(())
+}
+
+/// Serializes a document to an in-memory buffer instead of straight to disk,
+/// so callers can hash, filter, or redirect the bytes before (or instead of)
+/// writing them out.
+fn serialize_document(doc: &mut Document) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut buffer = Vec::new();
+    doc.save_to(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Hex-encoded SHA-256 digest of a byte slice, used to fingerprint chunk contents.
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
 
-        // Determine pages to delete
-        let pages_to_delete: Vec<u32> = all_pages.into_iter().filter(|p| !pages_to_keep.contains(p)).collect();
+/// Runs `filter` through the shell, with `$FILE` expanded to the chunk's
+/// would-be output path, and writes `bytes` to its stdin. Mirrors coreutils
+/// `split --filter`: the command is responsible for doing whatever it wants
+/// with the bytes (e.g. `gzip > $FILE.gz`), nothing is written to `output_path` directly.
+fn pipe_chunk_to_filter(filter: &str, output_path: &Path, bytes: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+    let expanded = filter.replace("$FILE", &output_path.to_string_lossy());
 
-        // Delete unwanted pages
-        // lopdf delete_pages expects 1-based page numbers
-        chunk_doc.delete_pages(&pages_to_delete);
+    let mut child = Command::new("sh").arg("-c").arg(&expanded).stdin(Stdio::piped()).spawn()?;
 
-        // Save the chunk document
-        chunk_doc.save(&output_path)?;
+    child
+        .stdin
+        .take()
+        .ok_or("failed to open filter command's stdin")?
+        .write_all(bytes)?;
 
-        println!("Saved chunk {} (pages {} to {}) to {:?}", chunk_index + 1, start_page + 1, end_page, output_path);
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(format!("filter command `{}` exited with {}", expanded, status).into());
     }
 
     Ok(())
 }
 
+/// Writes `bytes` under a content-addressed path, `<output_dir>/<first-2-hex>/<full-hex>.pdf`,
+/// skipping the write if a byte-identical chunk is already stored there.
+/// Returns the chunk's path and whether it was newly written.
+fn write_deduped_chunk(output_dir: &Path, digest: &str, bytes: &[u8]) -> Result<(PathBuf, bool), Box<dyn std::error::Error>> {
+    let shard_dir = output_dir.join(&digest[0..2]);
+    fs::create_dir_all(&shard_dir)?;
+
+    let chunk_path = shard_dir.join(format!("{}.pdf", digest));
+    if chunk_path.exists() {
+        return Ok((chunk_path, false));
+    }
+
+    fs::write(&chunk_path, bytes)?;
+    Ok((chunk_path, true))
+}
+
+/// Merges multiple single-chunk documents back into one, following lopdf's
+/// standard object-merge recipe: renumber every document's objects into a
+/// disjoint id space, then rebuild a single Catalog/Pages tree that
+/// references every page in order.
+fn merge_documents(mut docs: Vec<Document>) -> Result<Document, Box<dyn std::error::Error>> {
+    if docs.is_empty() {
+        return Err("no chunks to merge".into());
+    }
+
+    let mut max_id = 1;
+    let mut documents_pages = Vec::new();
+    let mut documents_objects = BTreeMap::new();
+
+    for doc in docs.iter_mut() {
+        doc.renumber_objects_with(max_id);
+        max_id = doc.max_id + 1;
+
+        documents_pages.extend(
+            doc.get_pages()
+                .into_values()
+                .map(|object_id| (object_id, doc.get_object(object_id).unwrap().to_owned())),
+        );
+        documents_objects.extend(doc.objects.clone());
+    }
+
+    let mut document = Document::with_version("1.5");
+    let mut catalog_object: Option<(ObjectId, Object)> = None;
+    let mut pages_object: Option<(ObjectId, Object)> = None;
+
+    for (object_id, object) in documents_objects.iter() {
+        match object.type_name().unwrap_or("") {
+            "Catalog" => {
+                catalog_object = Some((catalog_object.map_or(*object_id, |(id, _)| id), object.clone()));
+            }
+            "Pages" => {
+                pages_object = Some((pages_object.map_or(*object_id, |(id, _)| id), object.clone()));
+            }
+            "Page" => {} // Pages are rewired below, once the shared Pages object id is known
+            "Outlines" | "Outline" => {} // Bookmarks/outlines aren't meaningful across merged chunks
+            _ => {
+                document.objects.insert(*object_id, object.clone());
+            }
+        }
+    }
+
+    let (pages_id, pages_object) = pages_object.ok_or("chunk is missing a Pages object")?;
+    let (catalog_id, catalog_object) = catalog_object.ok_or("chunk is missing a Catalog object")?;
+
+    let mut pages_dict = pages_object.as_dict()?.clone();
+    pages_dict.set("Count", documents_pages.len() as u32);
+    pages_dict.set(
+        "Kids",
+        documents_pages.iter().map(|(id, _)| Object::Reference(*id)).collect::<Vec<_>>(),
+    );
+    document.objects.insert(pages_id, Object::Dictionary(pages_dict));
+
+    for (object_id, object) in documents_pages.iter() {
+        let mut page_dict = object.as_dict()?.clone();
+        page_dict.set("Parent", Object::Reference(pages_id));
+        document.objects.insert(*object_id, Object::Dictionary(page_dict));
+    }
+
+    let mut catalog_dict = catalog_object.as_dict()?.clone();
+    catalog_dict.set("Pages", Object::Reference(pages_id));
+    catalog_dict.remove(b"Outlines");
+    document.objects.insert(catalog_id, Object::Dictionary(catalog_dict));
+
+    document.trailer.set("Root", Object::Reference(catalog_id));
+    document.max_id = document.objects.len() as u32;
+    document.renumber_objects();
+    document.compress();
+
+    Ok(document)
+}
+
+/// Computes the page count for each chunk in page-size mode: every chunk holds
+/// `chunk_size` pages except the last, which holds whatever remains.
+fn page_size_chunk_lengths(num_pages: usize, chunk_size: usize) -> Vec<usize> {
+    if num_pages == 0 || chunk_size == 0 {
+        return Vec::new();
+    }
+    let mut lengths = Vec::new();
+    let mut remaining = num_pages;
+    while remaining > 0 {
+        let length = chunk_size.min(remaining);
+        lengths.push(length);
+        remaining -= length;
+    }
+    lengths
+}
+
+/// Computes a balanced per-chunk page count using the same partitioning GNU
+/// coreutils' `split -n` uses: the first `num_pages % num_chunks` chunks get
+/// `num_pages / num_chunks + 1` pages, the rest get `num_pages / num_chunks`.
+/// This guarantees exactly `num_chunks` chunks are emitted and that chunk
+/// sizes never differ by more than one page.
+fn balanced_chunk_lengths(num_pages: usize, num_chunks: usize) -> Vec<usize> {
+    let base = num_pages / num_chunks;
+    let remainder = num_pages % num_chunks;
+    (0..num_chunks)
+        .map(|i| if i < remainder { base + 1 } else { base })
+        .collect()
+}
+
+/// Distributes a total chunk budget across multiple inputs proportionally to
+/// their page counts: each file's initial share is
+/// `ceil(pages_i / avg_pages_per_chunk)`, where `avg_pages_per_chunk` is the
+/// overall average. That ceil-based allocation can overshoot `max_chunks`, so
+/// the largest shares are trimmed one chunk at a time until the total fits,
+/// without ever leaving a non-empty input with zero chunks.
+fn allocate_chunk_budget(page_counts: &[usize], max_chunks: usize) -> Vec<usize> {
+    let total_pages: usize = page_counts.iter().sum();
+    if total_pages == 0 {
+        return vec![0; page_counts.len()];
+    }
+
+    let avg_pages_per_chunk = (total_pages as f64 / max_chunks as f64).max(1.0);
+    let mut allocations: Vec<usize> = page_counts
+        .iter()
+        .map(|&pages| {
+            if pages == 0 {
+                0
+            } else {
+                ((pages as f64 / avg_pages_per_chunk).ceil() as usize).max(1)
+            }
+        })
+        .collect();
+
+    let mut total: usize = allocations.iter().sum();
+    while total > max_chunks {
+        let (idx, _) = allocations
+            .iter()
+            .enumerate()
+            .filter(|(_, &n)| n > 1)
+            .max_by_key(|(_, &n)| n)
+            .expect("max_chunks must be at least the number of non-empty inputs");
+        allocations[idx] -= 1;
+        total -= 1;
+    }
+
+    allocations
+}
+
+/// Clones `doc` and deletes every page outside the 0-based `[start_page, end_page)`
+/// range, leaving just the pages that belong to one chunk.
+fn extract_chunk(doc: &Document, num_pages: usize, start_page: usize, end_page: usize) -> Document {
+    let mut chunk_doc = doc.clone();
+
+    // Determine pages to keep (1-based)
+    let pages_to_keep: Vec<u32> = (start_page as u32 + 1..=end_page as u32).collect();
+    let all_pages: Vec<u32> = (1..=num_pages as u32).collect();
+
+    // Determine pages to delete
+    let pages_to_delete: Vec<u32> = all_pages.into_iter().filter(|p| !pages_to_keep.contains(p)).collect();
+
+    // Delete unwanted pages
+    // lopdf delete_pages expects 1-based page numbers
+    chunk_doc.delete_pages(&pages_to_delete);
+    chunk_doc
+}
+
+/// Computes per-chunk page counts for size-bytes mode. Starting from the
+/// current page, pages are added one at a time, re-serializing the candidate
+/// chunk each time, until adding the next page would exceed `max_bytes`.
+/// Always emits at least one page per chunk, even if a single page alone
+/// exceeds the limit (a warning is printed to stderr in that case).
+fn size_bytes_chunk_lengths(doc: &Document, num_pages: usize, max_bytes: usize) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let mut lengths = Vec::new();
+    let mut start_page = 0;
+
+    while start_page < num_pages {
+        let mut length = 1;
+        let single_page_size = serialized_chunk_len(doc, num_pages, start_page, start_page + length)?;
+        if single_page_size > max_bytes {
+            eprintln!(
+                "Warning: page {} alone serializes to {} bytes, over the {}-byte limit; emitting it as its own chunk anyway",
+                start_page + 1,
+                single_page_size,
+                max_bytes
+            );
+        }
+
+        while start_page + length < num_pages {
+            let candidate_size = serialized_chunk_len(doc, num_pages, start_page, start_page + length + 1)?;
+            if candidate_size > max_bytes {
+                break;
+            }
+            length += 1;
+        }
+
+        lengths.push(length);
+        start_page += length;
+    }
+
+    Ok(lengths)
+}
+
+/// Serializes the chunk spanning the 0-based `[start_page, end_page)` page
+/// range and returns its size in bytes, without writing anything to disk.
+fn serialized_chunk_len(doc: &Document, num_pages: usize, start_page: usize, end_page: usize) -> Result<usize, Box<dyn std::error::Error>> {
+    let mut chunk_doc = extract_chunk(doc, num_pages, start_page, end_page);
+    Ok(serialize_document(&mut chunk_doc)?.len())
+}
+
+/// Parses a human-readable byte size such as `5MB`, `512KB`, or a bare number of bytes.
+fn parse_byte_size(s: &str) -> Result<usize, String> {
+    let trimmed = s.trim();
+    let lower = trimmed.to_lowercase();
+
+    let (digits, multiplier) = if let Some(prefix) = lower.strip_suffix("mb") {
+        (prefix, 1024 * 1024)
+    } else if let Some(prefix) = lower.strip_suffix("kb") {
+        (prefix, 1024)
+    } else if let Some(prefix) = lower.strip_suffix('b') {
+        (prefix, 1)
+    } else {
+        (lower.as_str(), 1)
+    };
+
+    let value: f64 = digits
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid size `{}`: expected a number optionally followed by KB/MB", trimmed))?;
+
+    Ok((value * multiplier as f64).round() as usize)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,21 +635,25 @@ mod tests {
         }
 
         // Create dummy arguments for page size mode
-        let args = Args {
-            input: dummy_pdf_path.to_path_buf(),
+        let args = SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
             output: output_dir.to_path_buf(),
             mode: SplitMode::PageSize,
             chunk_size: 2, // Chunk size of 2 for testing
             num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: false,
         };
 
-        // Run the main logic with dummy arguments
-        main_with_args(&args)?; // Pass a reference
+        // Run the split logic with dummy arguments
+        run_split(&args)?;
 
         // Verify the output files
         let doc = Document::load(dummy_pdf_path)?;
         let num_pages = doc.get_pages().len();
-        let expected_chunks = (num_pages + args.chunk_size - 1) / args.chunk_size;
+        let expected_chunks = num_pages.div_ceil(args.chunk_size);
 
         let mut chunk_count = 0;
         if output_dir.exists() {
@@ -149,22 +685,23 @@ mod tests {
         }
 
         // Create dummy arguments for number of chunks mode
-        let args = Args {
-            input: dummy_pdf_path.to_path_buf(),
+        let args = SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
             output: output_dir.to_path_buf(),
             mode: SplitMode::NumChunks,
             chunk_size: 10, // Default chunk size (not used in this mode)
             num_chunks: Some(3), // Split into 3 chunks
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: false,
         };
 
-        // Run the main logic with dummy arguments
-        main_with_args(&args)?; // Pass a reference
+        // Run the split logic with dummy arguments
+        run_split(&args)?;
 
         // Verify the output files
-        let doc = Document::load(dummy_pdf_path)?;
-        let num_pages = doc.get_pages().len();
-        let num_chunks = args.num_chunks.unwrap_or(3);
-        let expected_chunks = num_chunks;
+        let expected_chunks = args.num_chunks.unwrap_or(3);
 
         let mut chunk_count = 0;
         if output_dir.exists() {
@@ -184,35 +721,502 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_split_then_merge_round_trip() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_merge_round_trip");
+        let merged_path = Path::new("test_output_merge_round_trip_merged.pdf");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+        if merged_path.exists() {
+            fs::remove_file(merged_path)?;
+        }
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: false,
+        })?;
+
+        // The manifest must record every chunk that was written.
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("manifest.json"))?)?;
+        let chunk_file_count = fs::read_dir(output_dir)?
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("pdf"))
+            .count();
+        assert_eq!(manifest.chunks.len(), chunk_file_count);
 
-    // Helper function to call main with specific arguments for testing
-    fn main_with_args(args: &Args) -> Result<(), Box<dyn std::error::Error>> { // Accept a reference
-        let doc = Document::load(&args.input)?;
+        run_merge(&MergeArgs {
+            input: output_dir.to_path_buf(),
+            output: merged_path.to_path_buf(),
+        })?;
+
+        let merged_doc = Document::load(merged_path)?;
+        let original_doc = Document::load(dummy_pdf_path)?;
+        assert_eq!(merged_doc.get_pages().len(), original_doc.get_pages().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_with_filter_suppresses_normal_output() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_filter");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: Some("cat > $FILE.filtered".to_string()),
+            dedup: false,
+        })?;
+
+        // The normal chunk_N.pdf files must not be created...
+        let normal_chunk_count = fs::read_dir(output_dir)?
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("pdf"))
+            .count();
+        assert_eq!(normal_chunk_count, 0, "filter mode must suppress normal file creation");
+
+        // ...but the filter command must still have run once per chunk.
+        let filtered_count = fs::read_dir(output_dir)?
+            .filter_map(Result::ok)
+            .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("filtered"))
+            .count();
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("manifest.json"))?)?;
+        assert_eq!(filtered_count, manifest.chunks.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_with_dedup_writes_content_addressed_chunks() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_dedup");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: true,
+        })?;
+
+        let index: BTreeMap<String, String> = serde_json::from_slice(&fs::read(output_dir.join("chunks.index"))?)?;
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("manifest.json"))?)?;
+        assert_eq!(index.len(), manifest.chunks.len());
+
+        // Every chunk's manifest path must resolve to a byte-identical, content-addressed file.
+        for chunk in &manifest.chunks {
+            let chunk_path = output_dir.join(&chunk.file);
+            assert!(chunk_path.exists(), "chunk file {:?} referenced by manifest must exist", chunk_path);
+            assert_eq!(sha256_hex(&fs::read(&chunk_path)?), chunk.sha256);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_and_dedup_are_mutually_exclusive() {
+        let err = run_split(&SplitArgs {
+            inputs: vec![PathBuf::from("test.pdf")],
+            output: PathBuf::from("test_output_filter_dedup_conflict"),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: Some("cat > $FILE".to_string()),
+            dedup: true,
+        });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_split_size_bytes_caps_chunk_size() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_size_bytes");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        // Cap each chunk just above the largest single page, so no page ever
+        // triggers the "a single page alone exceeds the cap" fallback, but
+        // comfortably below the size of any two pages combined.
+        let doc = Document::load(dummy_pdf_path)?;
         let num_pages = doc.get_pages().len();
-        fs::create_dir_all(&args.output)?;
-
-        let chunk_size = match args.mode {
-            SplitMode::PageSize => args.chunk_size,
-            SplitMode::NumChunks => {
-                let num_chunks = args.num_chunks.unwrap_or(3);
-                if num_chunks == 0 {
-                    return Err("Number of chunks cannot be zero.".into());
-                }
-                (num_pages + num_chunks - 1) / num_chunks
-            }
-        };
+        let max_single_page_size = (0..num_pages)
+            .map(|page| serialized_chunk_len(&doc, num_pages, page, page + 1))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .max()
+            .unwrap();
+        let max_bytes = max_single_page_size + 1;
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::SizeBytes,
+            chunk_size: 10,
+            num_chunks: None,
+            max_bytes: Some(max_bytes),
+            max_chunks: None,
+            filter: None,
+            dedup: false,
+        })?;
+
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("manifest.json"))?)?;
+        assert!(manifest.chunks.len() > 1, "the cap must force more than one chunk");
+        for chunk in manifest.chunks.iter().rev().skip(1) {
+            let chunk_bytes = fs::read(output_dir.join(&chunk.file))?;
+            assert!(
+                chunk_bytes.len() <= max_bytes,
+                "every chunk but possibly the last must be under the size limit"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_byte_size() {
+        assert_eq!(parse_byte_size("1024").unwrap(), 1024);
+        assert_eq!(parse_byte_size("5MB").unwrap(), 5 * 1024 * 1024);
+        assert_eq!(parse_byte_size("512KB").unwrap(), 512 * 1024);
+        assert_eq!(parse_byte_size("10mb").unwrap(), 10 * 1024 * 1024);
+        assert!(parse_byte_size("not-a-size").is_err());
+    }
+
+    #[test]
+    fn test_balanced_chunk_lengths_exact_division() {
+        let lengths = balanced_chunk_lengths(9, 3);
+        assert_eq!(lengths, vec![3, 3, 3]);
+    }
+
+    #[test]
+    fn test_balanced_chunk_lengths_awkward_ratio() {
+        // 9 pages into 4 chunks: the old ceil-based chunk_size (3) only ever
+        // produced 3 files. The balanced split must still emit all 4 chunks,
+        // with sizes differing by at most one page.
+        let lengths = balanced_chunk_lengths(9, 4);
+        assert_eq!(lengths.len(), 4, "must emit exactly the requested number of chunks");
+        assert_eq!(lengths.iter().sum::<usize>(), 9);
+        let max = *lengths.iter().max().unwrap();
+        let min = *lengths.iter().min().unwrap();
+        assert!(max - min <= 1, "chunk sizes must differ by at most one page");
+        assert_eq!(lengths, vec![3, 2, 2, 2]);
+    }
+
+    #[test]
+    fn test_balanced_chunk_lengths_more_chunks_than_pages() {
+        let lengths = balanced_chunk_lengths(2, 5);
+        assert_eq!(lengths.len(), 5);
+        assert_eq!(lengths.iter().sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn test_allocate_chunk_budget_proportional_to_page_count() {
+        // A 90-page file and a 10-page file sharing a budget of 10 chunks
+        // should get roughly 9x as many chunks as the smaller one.
+        let allocations = allocate_chunk_budget(&[90, 10], 10);
+        assert_eq!(allocations.len(), 2);
+        assert_eq!(allocations.iter().sum::<usize>(), 10);
+        assert!(allocations[0] > allocations[1]);
+    }
+
+    #[test]
+    fn test_allocate_chunk_budget_trims_down_to_total() {
+        // Ceil-based initial allocation can overshoot the budget; the trim
+        // step must bring the total back down to exactly max_chunks without
+        // ever leaving a file with zero chunks.
+        let allocations = allocate_chunk_budget(&[7, 7, 7], 10);
+        assert_eq!(allocations.iter().sum::<usize>(), 10);
+        assert!(allocations.iter().all(|&n| n >= 1));
+    }
+
+    #[test]
+    fn test_allocate_chunk_budget_never_zero_for_nonempty_input() {
+        // Even a file with very few pages relative to the others must get
+        // at least one chunk.
+        let allocations = allocate_chunk_budget(&[1, 1000], 4);
+        assert_eq!(allocations.iter().sum::<usize>(), 4);
+        assert!(allocations.iter().all(|&n| n >= 1));
+    }
+
+    #[test]
+    fn test_split_batch_distributes_budget_across_inputs() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let second_pdf_path = Path::new("test_batch_input_b.pdf");
+        let output_dir = Path::new("test_output_batch");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+        fs::copy(dummy_pdf_path, second_pdf_path)?;
+
+        let result = run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf(), second_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: Some(4),
+            filter: None,
+            dedup: false,
+        });
+
+        fs::remove_file(second_pdf_path)?;
+        result?;
+
+        // Each input gets its own index-prefixed subdirectory, named after its file stem.
+        let manifest_a: Manifest = serde_json::from_slice(&fs::read(output_dir.join("00_test").join("manifest.json"))?)?;
+        let manifest_b: Manifest =
+            serde_json::from_slice(&fs::read(output_dir.join("01_test_batch_input_b").join("manifest.json"))?)?;
+
+        assert_eq!(
+            manifest_a.chunks.len() + manifest_b.chunks.len(),
+            4,
+            "the combined chunk count must match the requested budget"
+        );
+        assert!(!manifest_a.chunks.is_empty());
+        assert!(!manifest_b.chunks.is_empty());
 
-        for (chunk_index, start_page) in (0..num_pages).step_by(chunk_size).enumerate() {
-            let end_page = (start_page + chunk_size).min(num_pages);
-            let output_path = args.output.join(format!("chunk_{}.pdf", chunk_index + 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batch_same_basename_inputs_dont_collide() -> Result<(), Box<dyn std::error::Error>> {
+        // Two --input files sharing a basename (here, the same path given
+        // twice) must not be written into the same sub-directory, or the
+        // second input's chunks would silently clobber the first's.
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_batch_same_basename");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf(), dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: Some(4),
+            filter: None,
+            dedup: false,
+        })?;
+
+        let manifest_0: Manifest = serde_json::from_slice(&fs::read(output_dir.join("00_test").join("manifest.json"))?)?;
+        let manifest_1: Manifest = serde_json::from_slice(&fs::read(output_dir.join("01_test").join("manifest.json"))?)?;
+
+        assert!(!manifest_0.chunks.is_empty(), "first input's output must survive the second input's split");
+        assert!(!manifest_1.chunks.is_empty());
+        assert_eq!(manifest_0.chunks.len() + manifest_1.chunks.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batch_skips_zero_page_input_without_erroring() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let empty_pdf_path = Path::new("test_batch_empty_input.pdf");
+        let output_dir = Path::new("test_output_batch_zero_page");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+        write_zero_page_pdf(empty_pdf_path)?;
+
+        let result = run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf(), empty_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: Some(4),
+            filter: None,
+            dedup: false,
+        });
+
+        fs::remove_file(empty_pdf_path)?;
+        result?;
+
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("00_test").join("manifest.json"))?)?;
+        assert!(!manifest.chunks.is_empty());
+        assert!(
+            !output_dir.join("01_test_batch_empty_input").exists(),
+            "a zero-page input must not get an output directory"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batch_max_chunks_only_counts_non_empty_inputs() -> Result<(), Box<dyn std::error::Error>> {
+        // A zero-page input doesn't draw from the chunk budget, so
+        // --max-chunks only needs to cover the non-empty inputs.
+        let dummy_pdf_path = Path::new("test.pdf");
+        let empty_pdf_path = Path::new("test_batch_empty_input_budget_check.pdf");
+        let output_dir = Path::new("test_output_batch_zero_page_budget_check");
 
-            let mut chunk_doc = doc.clone();
-            let pages_to_keep: Vec<u32> = (start_page as u32 + 1..=end_page as u32).collect();
-            let all_pages: Vec<u32> = (1..=num_pages as u32).collect();
-            let pages_to_delete: Vec<u32> = all_pages.into_iter().filter(|p| !pages_to_keep.contains(p)).collect();
-            chunk_doc.delete_pages(&pages_to_delete);
-            chunk_doc.save(&output_path)?;
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
         }
+        write_zero_page_pdf(empty_pdf_path)?;
+
+        let result = run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf(), empty_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: Some(1),
+            filter: None,
+            dedup: false,
+        });
+
+        fs::remove_file(empty_pdf_path)?;
+        result?;
+
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("00_test").join("manifest.json"))?)?;
+        assert_eq!(manifest.chunks.len(), 1);
+
+        Ok(())
+    }
+
+    /// Writes a minimal, valid, zero-page PDF to `path`, for exercising the
+    /// zero-page-input edge case in batch splitting.
+    fn write_zero_page_pdf(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        use lopdf::dictionary;
+
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.add_object(dictionary! {
+            "Type" => "Pages",
+            "Kids" => Vec::<Object>::new(),
+            "Count" => 0_u32,
+        });
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc.save(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batch_requires_max_chunks_for_multiple_inputs() {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let err = run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf(), dummy_pdf_path.to_path_buf()],
+            output: PathBuf::from("test_output_batch_missing_budget"),
+            mode: SplitMode::PageSize,
+            chunk_size: 2,
+            num_chunks: None,
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: false,
+        });
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_num_chunks_exceeding_page_count_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_num_chunks_exceeds_pages");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        let doc = Document::load(dummy_pdf_path)?;
+        let num_pages = doc.get_pages().len();
+
+        let err = run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::NumChunks,
+            chunk_size: 10,
+            num_chunks: Some(num_pages + 10),
+            max_bytes: None,
+            max_chunks: None,
+            filter: None,
+            dedup: false,
+        });
+
+        assert!(err.is_err(), "--num-chunks greater than the page count must be rejected");
+        assert!(
+            !output_dir.join("manifest.json").exists(),
+            "no manifest should be written when the request is rejected"
+        );
+        let chunk_file_count = fs::read_dir(output_dir)?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().and_then(|s| s.to_str()) == Some("pdf"))
+            .count();
+        assert_eq!(chunk_file_count, 0, "no junk chunk files should be written when the request is rejected");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_batch_single_input_with_max_chunks_overrides_mode() -> Result<(), Box<dyn std::error::Error>> {
+        // --max-chunks always forces balanced num-chunks partitioning, even
+        // for a single input that asked for a different mode; the requested
+        // --mode/--max-bytes must be overridden (with a warning), not
+        // silently honored.
+        let dummy_pdf_path = Path::new("test.pdf");
+        let output_dir = Path::new("test_output_batch_single_input_mode_override");
+
+        if output_dir.exists() {
+            fs::remove_dir_all(output_dir)?;
+        }
+
+        run_split(&SplitArgs {
+            inputs: vec![dummy_pdf_path.to_path_buf()],
+            output: output_dir.to_path_buf(),
+            mode: SplitMode::SizeBytes,
+            chunk_size: 10,
+            num_chunks: None,
+            max_bytes: Some(100),
+            max_chunks: Some(3),
+            filter: None,
+            dedup: false,
+        })?;
+
+        let manifest: Manifest = serde_json::from_slice(&fs::read(output_dir.join("00_test").join("manifest.json"))?)?;
+        assert_eq!(manifest.chunks.len(), 3, "--max-chunks must win over --mode size-bytes");
+
         Ok(())
     }
 }